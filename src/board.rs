@@ -1,4 +1,6 @@
-use anyhow::{Error, Result, bail, ensure};
+use crate::sparse_board::SparseBoard;
+use anyhow::{Error, Result, anyhow, bail, ensure};
+use std::str::FromStr;
 
 const ALIVE_CHAR: char = 'x';
 const DEAD_CHAR: char = '-';
@@ -55,10 +57,119 @@ impl Direction {
     }
 }
 
+// A Life-like rulestring in B/S notation, e.g. "B3/S23" for Conway's standard rule.
+// `birth[n]`/`survive[n]` say whether a dead/live cell with `n` live neighbors is
+// alive next generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: [bool; 9],
+    pub survive: [bool; 9],
+}
+
+impl Rule {
+    pub fn conway() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (b_part, s_part) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Rulestring must contain a '/' separating B and S sections"))?;
+
+        let parse_section = |part: &str, tag: char| -> Result<[bool; 9]> {
+            let part = part.trim();
+            let mut chars = part.chars();
+            let prefix = chars
+                .next()
+                .ok_or_else(|| anyhow!("Rulestring section must start with '{tag}'"))?;
+            ensure!(
+                prefix.to_ascii_uppercase() == tag.to_ascii_uppercase(),
+                "Rulestring section must start with '{tag}'"
+            );
+
+            let mut mask = [false; 9];
+            for c in chars {
+                let n = c
+                    .to_digit(10)
+                    .ok_or_else(|| anyhow!("Invalid character '{c}' in rulestring"))?
+                    as usize;
+                ensure!(n <= 8, "Neighbor counts in a rulestring must be 0-8");
+                ensure!(!mask[n], "Duplicate neighbor count {n} in rulestring");
+                mask[n] = true;
+            }
+            Ok(mask)
+        };
+
+        let birth = parse_section(b_part, 'b')?;
+        let survive = parse_section(s_part, 's')?;
+
+        Ok(Rule { birth, survive })
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "B")?;
+        for (n, &b) in self.birth.iter().enumerate() {
+            if b {
+                write!(f, "{n}")?;
+            }
+        }
+        write!(f, "/S")?;
+        for (n, &s) in self.survive.iter().enumerate() {
+            if s {
+                write!(f, "{n}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// How `neighbor_coords` treats a coordinate that falls off the edge of the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryMode {
+    // Off-board neighbors are always dead (the original, and default, behavior)
+    #[default]
+    Dead,
+    // The board wraps around on itself, so the top edge neighbors the bottom and
+    // the left edge neighbors the right
+    Toroidal,
+    // Off-board neighbors reflect back onto the board, as if it were bordered by a
+    // mirror
+    Mirror,
+}
+
+// Reflect an out-of-range index back onto `0..dim`, as if `dim` were bordered by a
+// mirror on each side
+fn mirror_index(idx: isize, dim: usize) -> usize {
+    let dim = dim as isize;
+    let idx = if idx < 0 {
+        -idx - 1
+    } else if idx >= dim {
+        2 * dim - idx - 1
+    } else {
+        idx
+    };
+    idx.clamp(0, dim - 1) as usize
+}
+
 #[derive(Debug, Clone)]
 pub struct Board {
     dims: Coords,
     cells: Vec<bool>,
+    rule: Rule,
+    boundary: BoundaryMode,
 }
 
 impl Board {
@@ -77,7 +188,30 @@ impl Board {
             None => vec![false; num_cells],
         };
 
-        Ok(Board { cells, dims })
+        Ok(Board {
+            cells,
+            dims,
+            rule: Rule::default(),
+            boundary: BoundaryMode::default(),
+        })
+    }
+
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    pub fn with_boundary_mode(mut self, boundary: BoundaryMode) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        self.boundary
     }
 
     pub fn dim_y(&self) -> usize {
@@ -97,7 +231,11 @@ impl Board {
         self.cells[self.index(coords)]
     }
 
-    fn set_alive(&mut self, coords: &Coords, alive: bool) {
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&alive| alive).count()
+    }
+
+    pub fn set_alive(&mut self, coords: &Coords, alive: bool) {
         let idx = self.index(coords);
         self.cells[idx] = alive;
     }
@@ -125,16 +263,31 @@ impl Board {
     }
 
     // Return the coordinates of the neighbor in the specified direction, or None if that would be
-    // off the board
+    // off the board under the current BoundaryMode
     fn neighbor_coords(&self, coords: &Coords, dir: &Direction) -> Option<Coords> {
         let offset = dir.offset();
-        let x = coords.x.checked_add_signed(offset.x)?;
-        let y = coords.y.checked_add_signed(offset.y)?;
 
-        if x >= self.dims.x || y >= self.dims.y {
-            None
-        } else {
-            Some(Coords { x, y })
+        match self.boundary {
+            BoundaryMode::Dead => {
+                let x = coords.x.checked_add_signed(offset.x)?;
+                let y = coords.y.checked_add_signed(offset.y)?;
+
+                if x >= self.dims.x || y >= self.dims.y {
+                    None
+                } else {
+                    Some(Coords { x, y })
+                }
+            }
+            BoundaryMode::Toroidal => {
+                let x = (coords.x as isize + offset.x).rem_euclid(self.dims.x as isize) as usize;
+                let y = (coords.y as isize + offset.y).rem_euclid(self.dims.y as isize) as usize;
+                Some(Coords { x, y })
+            }
+            BoundaryMode::Mirror => {
+                let x = mirror_index(coords.x as isize + offset.x, self.dims.x);
+                let y = mirror_index(coords.y as isize + offset.y, self.dims.y);
+                Some(Coords { x, y })
+            }
         }
     }
 
@@ -154,17 +307,19 @@ impl Board {
     }
 
     fn next_cell_state(&self, coords: &Coords) -> bool {
-        match (self.alive(coords), self.num_alive_neighbors(coords)) {
-            (true, 0..=1) => false, // Underpopulation
-            (true, 2..=3) => true,  // Survival
-            (true, 4..) => false,   // Overpopulation
-            (false, 3) => true,     // Reproduction
-            (false, _) => false,    // Stay dead
+        let n = self.num_alive_neighbors(coords);
+        if self.alive(coords) {
+            self.rule.survive[n]
+        } else {
+            self.rule.birth[n]
         }
     }
 
     pub fn next(&self) -> Self {
-        let mut next_board = Self::new(self.dims.clone(), None).unwrap();
+        let mut next_board = Self::new(self.dims.clone(), None)
+            .unwrap()
+            .with_rule(self.rule.clone())
+            .with_boundary_mode(self.boundary);
 
         for y in 0..self.dim_y() {
             for x in 0..self.dim_x() {
@@ -176,6 +331,32 @@ impl Board {
         next_board
     }
 
+    // Collect this board's live cells into a SparseBoard, the alternative
+    // backend whose cost scales with population rather than with dim_x * dim_y
+    pub fn to_sparse(&self) -> SparseBoard {
+        let live = (0..self.dim_y())
+            .flat_map(|y| (0..self.dim_x()).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.alive(&Coords { x, y }))
+            .map(|(x, y)| (x as isize, y as isize));
+        SparseBoard::new(live)
+    }
+
+    // Build a dense board of the given dimensions from a SparseBoard's live
+    // cells, dropping any that fall outside those dimensions
+    pub fn from_sparse(sparse: &SparseBoard, dims: Coords) -> Self {
+        let mut board = Self::new(dims, None).unwrap();
+        for (x, y) in sparse.live_cells() {
+            if x < 0 || y < 0 {
+                continue;
+            }
+            let (x, y) = (x as usize, y as usize);
+            if x < board.dim_x() && y < board.dim_y() {
+                board.set_alive(&Coords { x, y }, true);
+            }
+        }
+        board
+    }
+
     pub fn blinker() -> Self {
         "
         -----
@@ -205,6 +386,160 @@ impl Board {
         .try_into()
         .unwrap()
     }
+
+    // Parse the community-standard Run Length Encoded format: `#`-prefixed comment
+    // lines, a header `x = <w>, y = <h>, rule = <rulestring>`, then a data stream of
+    // `<count>b`/`<count>o` runs separated by `$` (end of row, or `<count>$` for
+    // that many blank rows), terminated by `!`
+    pub fn from_rle(s: &str) -> Result<Self> {
+        let mut width = None;
+        let mut height = None;
+        let mut rule = Rule::default();
+        let mut data = String::new();
+
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if width.is_none() && line.starts_with('x') {
+                for field in line.split(',') {
+                    let (key, value) = field
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("Malformed RLE header field: '{field}'"))?;
+                    let value = value.trim();
+                    match key.trim() {
+                        "x" => width = Some(value.parse::<usize>()?),
+                        "y" => height = Some(value.parse::<usize>()?),
+                        "rule" => rule = value.parse()?,
+                        key => bail!("Unknown RLE header field: '{key}'"),
+                    }
+                }
+                continue;
+            }
+            data.push_str(line);
+        }
+
+        let width = width.ok_or_else(|| anyhow!("RLE header is missing the 'x' field"))?;
+        let height = height.ok_or_else(|| anyhow!("RLE header is missing the 'y' field"))?;
+
+        let mut rows: Vec<Vec<bool>> = Vec::new();
+        let mut row: Vec<bool> = Vec::new();
+        let mut count_digits = String::new();
+
+        'outer: for c in data.chars() {
+            if c.is_ascii_digit() {
+                count_digits.push(c);
+                continue;
+            }
+
+            let count = if count_digits.is_empty() {
+                1
+            } else {
+                count_digits.parse()?
+            };
+            count_digits.clear();
+
+            match c {
+                'b' => row.extend(std::iter::repeat(false).take(count)),
+                'o' => row.extend(std::iter::repeat(true).take(count)),
+                '$' => {
+                    ensure!(row.len() <= width, "RLE row is longer than the declared width");
+                    row.resize(width, false);
+                    rows.push(std::mem::take(&mut row));
+                    for _ in 1..count {
+                        rows.push(vec![false; width]);
+                    }
+                }
+                '!' => {
+                    ensure!(row.len() <= width, "RLE row is longer than the declared width");
+                    row.resize(width, false);
+                    rows.push(std::mem::take(&mut row));
+                    break 'outer;
+                }
+                _ => bail!("Unexpected character '{c}' in RLE data"),
+            }
+        }
+
+        if !row.is_empty() {
+            ensure!(row.len() <= width, "RLE row is longer than the declared width");
+            row.resize(width, false);
+            rows.push(row);
+        }
+
+        while rows.len() < height {
+            rows.push(vec![false; width]);
+        }
+        ensure!(
+            rows.len() == height,
+            "RLE pattern has more rows than its declared height"
+        );
+
+        let cells: Vec<bool> = rows.into_iter().flatten().collect();
+
+        Ok(Board::new(Coords { x: width, y: height }, Some(cells))?.with_rule(rule))
+    }
+
+    // Emit this board as RLE: a header followed by run-length-encoded rows, with
+    // trailing dead cells on a row and runs of fully dead rows collapsed into `$`
+    // counts
+    pub fn to_rle(&self) -> String {
+        let mut body = String::new();
+        let mut blank_run = 0usize;
+
+        for y in 0..self.dim_y() {
+            let row: Vec<bool> = (0..self.dim_x())
+                .map(|x| self.alive(&Coords { x, y }))
+                .collect();
+            let last_alive = row.iter().rposition(|&a| a);
+
+            let Some(last) = last_alive else {
+                blank_run += 1;
+                continue;
+            };
+
+            if blank_run > 0 {
+                // A blank run's `$` count must also cover the terminator of the row
+                // that precedes it, unless these are leading blank rows with no
+                // preceding row to terminate
+                let count = if body.is_empty() {
+                    blank_run
+                } else {
+                    blank_run + 1
+                };
+                if count > 1 {
+                    body.push_str(&count.to_string());
+                }
+                body.push('$');
+                blank_run = 0;
+            } else if !body.is_empty() {
+                body.push('$');
+            }
+
+            let mut i = 0;
+            while i <= last {
+                let alive = row[i];
+                let run_start = i;
+                while i <= last && row[i] == alive {
+                    i += 1;
+                }
+                let run_len = i - run_start;
+                if run_len > 1 {
+                    body.push_str(&run_len.to_string());
+                }
+                body.push(if alive { 'o' } else { 'b' });
+            }
+        }
+        body.push('!');
+
+        format!(
+            "x = {}, y = {}, rule = {}\n{}\n",
+            self.dim_x(),
+            self.dim_y(),
+            self.rule,
+            body
+        )
+    }
 }
 
 // Helper to easily turn human readable strings into a board
@@ -259,3 +594,55 @@ impl std::fmt::Display for Board {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rle_round_trips_through_a_single_interior_blank_row() {
+        let board: Board = "
+            x--
+            ---
+            --x
+            "
+        .try_into()
+        .unwrap();
+
+        let round_tripped = Board::from_rle(&board.to_rle()).unwrap();
+
+        assert_eq!(board.to_string(), round_tripped.to_string());
+    }
+
+    #[test]
+    fn from_rle_rejects_a_row_longer_than_the_declared_width() {
+        let rle = "x = 2, y = 1, rule = B3/S23\n3o!";
+
+        assert!(Board::from_rle(rle).is_err());
+    }
+
+    #[test]
+    fn rle_round_trips_through_multiple_interior_blank_rows() {
+        let board: Board = "
+            x--
+            ---
+            ---
+            --x
+            "
+        .try_into()
+        .unwrap();
+
+        let round_tripped = Board::from_rle(&board.to_rle()).unwrap();
+
+        assert_eq!(board.to_string(), round_tripped.to_string());
+    }
+
+    #[test]
+    fn rle_round_trips_gosper() {
+        let board = Board::gosper();
+
+        let round_tripped = Board::from_rle(&board.to_rle()).unwrap();
+
+        assert_eq!(board.to_string(), round_tripped.to_string());
+    }
+}
@@ -1,8 +1,13 @@
 mod board;
+mod pattern;
+mod sparse_board;
 use anyhow::Result;
-use board::{Board, Coords};
+use board::{Board, BoundaryMode, Coords, Rule};
 use leptos::{html::Canvas, prelude::*};
+use pattern::Pattern;
+use sparse_board::SparseBoard;
 use std::f64;
+use strum::IntoEnumIterator;
 use wasm_bindgen::prelude::*;
 
 const CELL_SIZE: usize = 10;
@@ -41,6 +46,46 @@ fn App(canvas_height: usize, canvas_width: usize, initial_board: Board) -> impl
     let (interval_id, set_interval_id) = signal(None::<i32>);
     let (interval_seconds, set_interval_seconds) = signal(0.05f64);
 
+    // State for click-and-drag editing: whether the mouse button is currently
+    // held down over the canvas, and the alive/dead state a drag is painting
+    // towards (decided on mousedown so the whole drag paints one target state)
+    let (is_painting, set_is_painting) = signal(false);
+    let (paint_alive, set_paint_alive) = signal(false);
+
+    // The rulestring currently typed into the rule input; kept separate from the
+    // board's actual Rule so a user can type a partial/invalid string without it
+    // being applied
+    let (rule_text, set_rule_text) = signal("B3/S23".to_string());
+
+    // Stats HUD state: generation count and how long the last `next()` took
+    let (generation, set_generation) = signal(0usize);
+    let (step_duration_ms, set_step_duration_ms) = signal(0.0f64);
+
+    // The contents of the RLE textarea, used for both export (populated from the
+    // board) and import (parsed back into a board)
+    let (rle_text, set_rle_text) = signal(String::new());
+
+    // Whether to step the board via the sparse backend instead of the dense one.
+    // The sparse backend always runs standard Conway rules on an unbounded grid,
+    // so stepping through it ignores the board's current Rule/BoundaryMode and
+    // clips the result back to the canvas's fixed dimensions for drawing
+    let (use_sparse, set_use_sparse) = signal(false);
+
+    // The sparse backend's live-cell state, carried across generations while
+    // use_sparse is enabled so each step is O(population) instead of requiring
+    // a fresh dense->sparse scan. None while the sparse backend is off
+    let (sparse_state, set_sparse_state) = signal(None::<SparseBoard>);
+
+    // Re-derive the cached sparse state from the current dense board whenever
+    // the board is replaced or edited from outside `advance` (painting,
+    // picking a pattern, importing RLE), so the sparse backend doesn't keep
+    // stepping a population that no longer matches what's on screen
+    let resync_sparse_state = move || {
+        if use_sparse.get_untracked() {
+            set_sparse_state.set(Some(board.get_untracked().to_sparse()));
+        }
+    };
+
     // Effect to redraw canvas whenever board changes
     Effect::new(move |_| {
         // Get canvas and context
@@ -54,21 +99,53 @@ fn App(canvas_height: usize, canvas_width: usize, initial_board: Board) -> impl
         draw(context, board.get());
     });
 
+    // Advance the board by one generation, timing the step with the browser's
+    // high-resolution clock and bumping the generation counter
+    let advance = move || {
+        let performance = web_sys::window().unwrap().performance().unwrap();
+        let start = performance.now();
+        if let Some(sparse) = sparse_state.get_untracked() {
+            let next = sparse.next();
+            let current = board.get_untracked();
+            let dims = Coords {
+                x: current.dim_x(),
+                y: current.dim_y(),
+            };
+            set_board.set(Board::from_sparse(&next, dims));
+            set_sparse_state.set(Some(next));
+        } else {
+            set_board.update(|b| *b = b.next());
+        }
+        set_step_duration_ms.set(performance.now() - start);
+        set_generation.update(|g| *g += 1);
+    };
+
     // Manual step function
     let step = move |_: web_sys::MouseEvent| {
-        set_board.update(|b| *b = b.next());
+        advance();
+    };
+
+    // Convert the dense board to sparse once on enable so later steps only
+    // touch the live population; drop the cached sparse state on disable so
+    // it doesn't go stale while the dense backend is in control
+    let on_sparse_toggle = move |ev: web_sys::Event| {
+        let target = ev.target().unwrap();
+        let input = target.dyn_into::<web_sys::HtmlInputElement>().unwrap();
+        let enabled = input.checked();
+        set_use_sparse.set(enabled);
+        if enabled {
+            resync_sparse_state();
+        } else {
+            set_sparse_state.set(None);
+        }
     };
 
     // Function to start the interval with current settings
     let start_interval = {
-        let set_board = set_board.clone();
         let set_interval_id = set_interval_id.clone();
         move || {
-            let callback = Closure::wrap(Box::new({
-                let set_board = set_board.clone();
-                move || {
-                    set_board.update(|b| *b = b.next());
-                }
+            let callback = Closure::wrap(Box::new(move || {
+                advance();
             }) as Box<dyn FnMut()>);
 
             let id = web_sys::window()
@@ -92,6 +169,144 @@ fn App(canvas_height: usize, canvas_width: usize, initial_board: Board) -> impl
         set_interval_id.set(None);
     };
 
+    // Convert a mouse event's position to board coordinates by reading the
+    // canvas's bounding rect at event time (it isn't cached, since Leptos can
+    // reflow the layout between frames, which would otherwise desync the
+    // pixel->cell mapping if the canvas is scrolled or resized)
+    let event_to_coords = move |ev: &web_sys::MouseEvent| -> Option<Coords> {
+        let canvas = canvas_ref.get()?;
+        let rect = canvas.get_bounding_client_rect();
+        let x = ev.client_x() as f64 - rect.left();
+        let y = ev.client_y() as f64 - rect.top();
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let coords = Coords {
+            x: x as usize / CELL_SIZE,
+            y: y as usize / CELL_SIZE,
+        };
+        let b = board.get_untracked();
+        if coords.x >= b.dim_x() || coords.y >= b.dim_y() {
+            return None;
+        }
+        Some(coords)
+    };
+
+    // Paint the given coords to the in-progress drag's target state
+    let paint = move |coords: Coords| {
+        let target = paint_alive.get();
+        set_board.update(|b| b.set_alive(&coords, target));
+        resync_sparse_state();
+    };
+
+    let on_mouse_down = {
+        let stop_interval = stop_interval.clone();
+        move |ev: web_sys::MouseEvent| {
+            let Some(coords) = event_to_coords(&ev) else {
+                return;
+            };
+
+            // Pause auto-play while editing
+            if is_running.get() {
+                stop_interval();
+                set_is_running.set(false);
+            }
+
+            let target = !board.get_untracked().alive(&coords);
+            set_paint_alive.set(target);
+            set_is_painting.set(true);
+            paint(coords);
+        }
+    };
+
+    let on_mouse_move = move |ev: web_sys::MouseEvent| {
+        if !is_painting.get() {
+            return;
+        }
+        if let Some(coords) = event_to_coords(&ev) {
+            paint(coords);
+        }
+    };
+
+    let on_mouse_up = move |_: web_sys::MouseEvent| {
+        set_is_painting.set(false);
+    };
+
+    let on_rule_input = move |ev: web_sys::Event| {
+        let target = ev.target().unwrap();
+        let input = target.dyn_into::<web_sys::HtmlInputElement>().unwrap();
+        let value = input.value();
+        set_rule_text.set(value.clone());
+        if let Ok(rule) = value.parse::<Rule>() {
+            set_board.update(|b| *b = b.clone().with_rule(rule));
+        }
+    };
+
+    let on_boundary_change = move |ev: web_sys::Event| {
+        let target = ev.target().unwrap();
+        let select = target.dyn_into::<web_sys::HtmlSelectElement>().unwrap();
+        let boundary = match select.value().as_str() {
+            "Toroidal" => BoundaryMode::Toroidal,
+            "Mirror" => BoundaryMode::Mirror,
+            _ => BoundaryMode::Dead,
+        };
+        set_board.update(|b| *b = b.clone().with_boundary_mode(boundary));
+    };
+
+    // Stamp the chosen Pattern into a fresh board of the current dimensions,
+    // centered, and reset the generation counter to start a new run
+    let on_pattern_change = move |ev: web_sys::Event| {
+        let target = ev.target().unwrap();
+        let select = target.dyn_into::<web_sys::HtmlSelectElement>().unwrap();
+        let value = select.value();
+        let Some(pattern) = Pattern::iter().find(|p| p.to_string() == value) else {
+            return;
+        };
+
+        let current = board.get_untracked();
+        let dims = Coords {
+            x: current.dim_x(),
+            y: current.dim_y(),
+        };
+        let fresh = Board::new(dims, None)
+            .unwrap()
+            .with_rule(current.rule().clone())
+            .with_boundary_mode(current.boundary_mode());
+
+        let pattern_board = pattern.board();
+        let offset = Coords {
+            x: (fresh.dim_x().saturating_sub(pattern_board.dim_x())) / 2,
+            y: (fresh.dim_y().saturating_sub(pattern_board.dim_y())) / 2,
+        };
+
+        if let Ok(new_board) = fresh.add(pattern_board, offset) {
+            set_board.set(new_board);
+            set_generation.set(0);
+            resync_sparse_state();
+        }
+    };
+
+    let on_rle_input = move |ev: web_sys::Event| {
+        let target = ev.target().unwrap();
+        let textarea = target.dyn_into::<web_sys::HtmlTextAreaElement>().unwrap();
+        set_rle_text.set(textarea.value());
+    };
+
+    // Write the current board into the RLE textarea
+    let export_rle = move |_: web_sys::MouseEvent| {
+        set_rle_text.set(board.get_untracked().to_rle());
+    };
+
+    // Replace the board with whatever is parsed from the RLE textarea and
+    // restart the generation count
+    let import_rle = move |_: web_sys::MouseEvent| {
+        if let Ok(new_board) = Board::from_rle(&rle_text.get_untracked()) {
+            set_board.set(new_board);
+            set_generation.set(0);
+            resync_sparse_state();
+        }
+    };
+
     // Auto-play toggle function
     let toggle_auto_play = {
         let start_interval = start_interval.clone();
@@ -158,6 +373,10 @@ fn App(canvas_height: usize, canvas_width: usize, initial_board: Board) -> impl
                 height=canvas_height
                 width=canvas_width
                 style="background-color: #333333; display: block;"
+                on:mousedown=on_mouse_down
+                on:mousemove=on_mouse_move
+                on:mouseup=on_mouse_up
+                on:mouseleave=on_mouse_up
             ></canvas>
             <div style="margin-top: 10px;">
                 <button on:click=step style="padding: 10px 20px; font-size: 16px; margin-right: 10px;">
@@ -180,6 +399,62 @@ fn App(canvas_height: usize, canvas_width: usize, initial_board: Board) -> impl
                 />
                 <button on:click=increase_interval style="padding: 5px 10px; font-size: 14px;">"+"</button>
             </div>
+            <div style="margin-top: 10px; display: flex; align-items: center; gap: 10px;">
+                <label>
+                    <input
+                        type="checkbox"
+                        on:change=on_sparse_toggle
+                        prop:checked=move || use_sparse.get()
+                    />
+                    " Sparse backend (standard B3/S23, ignores Rule/Boundary)"
+                </label>
+            </div>
+            <div style="margin-top: 10px; display: flex; align-items: center; gap: 20px;">
+                <span>{move || format!("Generation: {}", generation.get())}</span>
+                <span>{move || format!("Population: {}", board.get().population())}</span>
+                <span>{move || format!("Step time: {:.2} ms", step_duration_ms.get())}</span>
+            </div>
+            <div style="margin-top: 10px; display: flex; align-items: center; gap: 10px;">
+                <span>"Rule (B/S):"</span>
+                <input
+                    type="text"
+                    prop:value=move || rule_text.get()
+                    on:input=on_rule_input
+                    style="width: 100px; padding: 5px; text-align: center;"
+                />
+            </div>
+            <div style="margin-top: 10px; display: flex; align-items: center; gap: 10px;">
+                <span>"Boundary:"</span>
+                <select on:change=on_boundary_change style="padding: 5px;">
+                    <option value="Dead">"Dead"</option>
+                    <option value="Toroidal">"Toroidal"</option>
+                    <option value="Mirror">"Mirror"</option>
+                </select>
+            </div>
+            <div style="margin-top: 10px; display: flex; align-items: center; gap: 10px;">
+                <span>"Pattern:"</span>
+                <select on:change=on_pattern_change style="padding: 5px;">
+                    {Pattern::iter()
+                        .map(|p| {
+                            let name = p.to_string();
+                            view! { <option value=name.clone()>{name}</option> }
+                        })
+                        .collect::<Vec<_>>()}
+                </select>
+            </div>
+            <div style="margin-top: 10px; display: flex; flex-direction: column; gap: 10px;">
+                <span>"RLE:"</span>
+                <textarea
+                    rows="6"
+                    prop:value=move || rle_text.get()
+                    on:input=on_rle_input
+                    style="width: 400px; font-family: monospace;"
+                ></textarea>
+                <div style="display: flex; gap: 10px;">
+                    <button on:click=export_rle style="padding: 5px 10px;">"Export"</button>
+                    <button on:click=import_rle style="padding: 5px 10px;">"Import"</button>
+                </div>
+            </div>
         </div>
     }
 }
@@ -0,0 +1,64 @@
+use crate::board::Board;
+use strum::{Display, EnumIter};
+
+// A classic, named Life pattern that can be stamped onto a Board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter)]
+pub enum Pattern {
+    Blinker,
+    Glider,
+    #[strum(to_string = "Lightweight Spaceship")]
+    Lwss,
+    Pulsar,
+    #[strum(to_string = "Gosper Glider Gun")]
+    GosperGliderGun,
+    #[strum(to_string = "R-pentomino")]
+    RPentomino,
+}
+
+impl Pattern {
+    pub fn board(&self) -> Board {
+        match self {
+            Self::Blinker => Board::blinker(),
+            Self::Glider => "
+                -x-
+                --x
+                xxx
+                "
+            .try_into()
+            .unwrap(),
+            Self::Lwss => "
+                -xxxx
+                x---x
+                ----x
+                x--x-
+                "
+            .try_into()
+            .unwrap(),
+            Self::Pulsar => "
+                --xxx---xxx--
+                -------------
+                x----x-x----x
+                x----x-x----x
+                x----x-x----x
+                --xxx---xxx--
+                -------------
+                --xxx---xxx--
+                x----x-x----x
+                x----x-x----x
+                x----x-x----x
+                -------------
+                --xxx---xxx--
+                "
+            .try_into()
+            .unwrap(),
+            Self::GosperGliderGun => Board::gosper(),
+            Self::RPentomino => "
+                -xx
+                xx-
+                -x-
+                "
+            .try_into()
+            .unwrap(),
+        }
+    }
+}
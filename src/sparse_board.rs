@@ -0,0 +1,113 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+// The 8 neighbor offsets, independent of any origin or bounds
+const NEIGHBOR_OFFSETS: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+// A Conway's Life board that stores only live cells, so memory and per-generation
+// work scale with population rather than with a fixed width*height rectangle.
+// Coordinates are signed and unbounded, so patterns are free to grow or drift
+// in any direction instead of being clipped to a bounding box.
+#[derive(Debug, Clone, Default)]
+pub struct SparseBoard {
+    live: FxHashSet<(isize, isize)>,
+}
+
+impl SparseBoard {
+    pub fn new(live: impl IntoIterator<Item = (isize, isize)>) -> Self {
+        SparseBoard {
+            live: live.into_iter().collect(),
+        }
+    }
+
+    pub fn alive(&self, x: isize, y: isize) -> bool {
+        self.live.contains(&(x, y))
+    }
+
+    pub fn population(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.live.iter().copied()
+    }
+
+    pub fn next(&self) -> Self {
+        let mut neighbor_counts: FxHashMap<(isize, isize), u8> = FxHashMap::default();
+        for &(x, y) in &self.live {
+            for (dx, dy) in NEIGHBOR_OFFSETS {
+                *neighbor_counts.entry((x + dx, y + dy)).or_insert(0) += 1;
+            }
+        }
+
+        // A live cell with no live neighbors never shows up in neighbor_counts, so the
+        // live cells are added to the candidates to have every live cell evaluated;
+        // such a cell has a count of 0 and dies under B3/S23
+        let candidates: FxHashSet<(isize, isize)> = neighbor_counts
+            .keys()
+            .copied()
+            .chain(self.live.iter().copied())
+            .collect();
+
+        let live = candidates
+            .into_iter()
+            .filter(|coords| {
+                let count = neighbor_counts.get(coords).copied().unwrap_or(0);
+                matches!((self.live.contains(coords), count), (true, 2..=3) | (false, 3))
+            })
+            .collect();
+
+        SparseBoard { live }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_cells(board: &SparseBoard) -> Vec<(isize, isize)> {
+        let mut cells: Vec<_> = board.live_cells().collect();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn isolated_cell_dies_of_underpopulation() {
+        let board = SparseBoard::new([(0, 0)]);
+
+        let next = board.next();
+
+        assert_eq!(next.population(), 0);
+    }
+
+    #[test]
+    fn blinker_oscillates_between_vertical_and_horizontal() {
+        let vertical = SparseBoard::new([(0, -1), (0, 0), (0, 1)]);
+
+        let horizontal = vertical.next();
+        assert_eq!(sorted_cells(&horizontal), vec![(-1, 0), (0, 0), (1, 0)]);
+
+        let back_to_vertical = horizontal.next();
+        assert_eq!(
+            sorted_cells(&back_to_vertical),
+            vec![(0, -1), (0, 0), (0, 1)]
+        );
+    }
+
+    #[test]
+    fn block_is_stable() {
+        let block = SparseBoard::new([(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+        let next = block.next();
+
+        assert_eq!(sorted_cells(&next), sorted_cells(&block));
+    }
+}